@@ -0,0 +1,56 @@
+use crate::error::PortfolioError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Exchange rates into a single reporting/base currency, keyed by ISO currency code.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CurrencyConverter {
+    pub base_currency: String,
+    pub rates: HashMap<String, Decimal>,
+}
+
+impl CurrencyConverter {
+    /// Converts `amount` denominated in `from_currency` into the base currency. Errors if
+    /// `from_currency` isn't the base currency and has no configured rate, rather than
+    /// silently treating it as already being in the base currency.
+    pub fn convert(&self, amount: Decimal, from_currency: &str) -> Result<Decimal, PortfolioError> {
+        if from_currency == self.base_currency {
+            return Ok(amount);
+        }
+        let rate = self
+            .rates
+            .get(from_currency)
+            .ok_or_else(|| PortfolioError::UnknownCurrency(from_currency.to_string()))?;
+        amount.checked_mul(*rate).ok_or(PortfolioError::Overflow("currency conversion"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converter() -> CurrencyConverter {
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), Decimal::new(135, 2));
+        CurrencyConverter { base_currency: "CAD".to_string(), rates }
+    }
+
+    #[test]
+    fn base_currency_passes_through_unchanged() {
+        let result = converter().convert(Decimal::from(100), "CAD").unwrap();
+        assert_eq!(result, Decimal::from(100));
+    }
+
+    #[test]
+    fn configured_rate_converts_to_base_currency() {
+        let result = converter().convert(Decimal::from(100), "USD").unwrap();
+        assert_eq!(result, Decimal::new(135, 0));
+    }
+
+    #[test]
+    fn unconfigured_currency_is_an_error() {
+        let result = converter().convert(Decimal::from(100), "EUR");
+        assert!(matches!(result, Err(PortfolioError::UnknownCurrency(currency)) if currency == "EUR"));
+    }
+}
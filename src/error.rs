@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// Errors produced while computing portfolio figures.
+#[derive(Debug)]
+pub enum PortfolioError {
+    DivisionByZero(&'static str),
+    Overflow(&'static str),
+    QuoteUnavailable(String),
+    MissingProviderConfig(&'static str),
+    UnknownCurrency(String),
+}
+
+impl fmt::Display for PortfolioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PortfolioError::DivisionByZero(context) => {
+                write!(f, "division by zero while computing {}", context)
+            }
+            PortfolioError::Overflow(context) => {
+                write!(f, "numeric overflow while computing {}", context)
+            }
+            PortfolioError::QuoteUnavailable(symbol) => {
+                write!(f, "could not fetch a live quote for {}", symbol)
+            }
+            PortfolioError::MissingProviderConfig(provider) => {
+                write!(f, "missing config block for quote provider {}", provider)
+            }
+            PortfolioError::UnknownCurrency(currency) => {
+                write!(f, "no exchange rate configured for currency {}", currency)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PortfolioError {}
@@ -1,143 +1,214 @@
+mod currency;
+mod error;
+mod monte_carlo;
+mod options;
+mod quote_provider;
+mod rebalancer;
+
+use chrono::NaiveDate;
+use currency::CurrencyConverter;
+use error::PortfolioError;
+use options::OptionHolding;
+use quote_provider::QuoteProviderConfig;
+use rebalancer::RebalancingConfig;
+use rust_decimal::prelude::*;
 use serde::{Deserialize, Serialize};
-use serde_json::Result;
+use std::error::Error;
 use std::fs;
-use std::fmt;
-use std::cmp::Ordering;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Config {
+    quote_provider: QuoteProviderConfig,
+    rebalancing: RebalancingConfig,
+    currency: CurrencyConverter,
+    /// Risk-free rate used for option valuation, as a percentage (e.g. `5.0` for 5%).
+    risk_free_rate: f64,
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 struct Data {
     stocks: Vec<Stock>,
-    annual_expenses: f64,
+    options: Vec<OptionHolding>,
+    annual_expenses: Decimal,
     target_retirement_age: i32,
     current_age: i32,
     target_growth_rate: f64,
-    usd_to_cad_exchange_rate: f64,
-    expected_contribution: f64,
+    return_volatility: f64,
+    monte_carlo_seed: u64,
+    expected_contribution: Decimal,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 struct Stock {
     symbol: String,
-    quote: f64,
+    quote: Decimal,
     number_of_shares: i64,
     target_allocation: f64,
-    is_usd: bool,
+    min_allocation: f64,
+    max_allocation: f64,
+    currency: String,
 }
 
-#[derive(PartialEq)]
-struct CalculationResult{
-    symbol: String,
-    new_number_of_shares: i64,
-    cost: f64,
+fn calc_value_of_stock(stock: &Stock, converter: &CurrencyConverter) -> Result<Decimal, PortfolioError> {
+    let value = stock
+        .quote
+        .checked_mul(Decimal::from(stock.number_of_shares))
+        .ok_or(PortfolioError::Overflow("stock value"))?;
+    converter.convert(value, &stock.currency)
 }
 
-impl PartialOrd for CalculationResult{
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.cost.partial_cmp(&other.cost)
-    }
+fn calc_portfolio_val(
+    data: &Data,
+    converter: &CurrencyConverter,
+    risk_free_rate: f64,
+    valuation_date: NaiveDate,
+) -> Result<Decimal, PortfolioError> {
+    let stocks_value = data.stocks.iter().try_fold(Decimal::ZERO, |acc, stock| {
+        let value = calc_value_of_stock(stock, converter)?;
+        acc.checked_add(value).ok_or(PortfolioError::Overflow("portfolio total"))
+    })?;
+    data.options.iter().try_fold(stocks_value, |acc, option| {
+        let value = options::calc_value_of_option(option, risk_free_rate, valuation_date, converter)?;
+        acc.checked_add(value).ok_or(PortfolioError::Overflow("portfolio total"))
+    })
 }
 
-impl fmt::Display for CalculationResult{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Buy {} shares in {} for a cost of {}", self.new_number_of_shares, self.symbol, self.cost)
+fn print_where_to_contribute(
+    data: Data,
+    config: &RebalancingConfig,
+    converter: &CurrencyConverter,
+    risk_free_rate: f64,
+    valuation_date: NaiveDate,
+) -> Result<(), PortfolioError> {
+    let total_value = calc_portfolio_val(&data, converter, risk_free_rate, valuation_date)?;
+    let results = rebalancer::rebalance(&data, total_value, config, converter)?;
+
+    println!("To fix allocations, make the following trades");
+    for result in results {
+        println!("{}", result);
     }
-}
 
-fn calc_value_of_stock(stock: &Stock, usd_to_cad_exchange_rate: f64) -> f64{
-    if stock.is_usd {
-        stock.quote * (stock.number_of_shares as f64) * usd_to_cad_exchange_rate
-    }else{
-        stock.quote * (stock.number_of_shares as f64)
-    }
+    Ok(())
 }
 
-fn calc_portfolio_val(stocks: Vec<Stock>, usd_to_cad_exchange_rate: f64) -> f64 {
-    stocks.iter().map(|stock| calc_value_of_stock(stock, usd_to_cad_exchange_rate)).sum()
-}
+fn print_how_close_to_retirement(
+    data: Data,
+    converter: &CurrencyConverter,
+    risk_free_rate: f64,
+    valuation_date: NaiveDate,
+) -> Result<(), PortfolioError> {
+    let total_value = calc_portfolio_val(&data, converter, risk_free_rate, valuation_date)?;
+    let years_to_pass = (data.target_retirement_age - data.current_age).max(0);
+    // Once there are no more years left to pass, no further contribution will be made, so the
+    // present value is just `total_value` — matching monte_carlo::project's own short-circuit.
+    let present_value = if years_to_pass <= 0 {
+        total_value
+    } else {
+        total_value
+            .checked_add(data.expected_contribution)
+            .ok_or(PortfolioError::Overflow("contributed value"))?
+    };
+    let rate_as_decimal = Decimal::from_f64(data.target_growth_rate)
+        .ok_or(PortfolioError::Overflow("growth rate"))?
+        .checked_div(Decimal::ONE_HUNDRED)
+        .ok_or(PortfolioError::DivisionByZero("growth rate"))?;
+    let multiplier = Decimal::ONE
+        .checked_add(rate_as_decimal)
+        .ok_or(PortfolioError::Overflow("growth multiplier"))?;
+    let retirement_rule = Decimal::new(4, 0);
+    let target_retirement_portfolio_value = data.annual_expenses.checked_div(
+        retirement_rule
+            .checked_div(Decimal::ONE_HUNDRED)
+            .ok_or(PortfolioError::DivisionByZero("withdrawal rate"))?,
+    ).ok_or(PortfolioError::DivisionByZero("target retirement value"))?;
+    let fully_grown_portfolio = (0..years_to_pass).try_fold(present_value, |acc, _| {
+        acc.checked_mul(multiplier)
+    }).ok_or(PortfolioError::Overflow("compounded portfolio value"))?;
+    let percentage_of_retirement_value = fully_grown_portfolio
+        .checked_div(target_retirement_portfolio_value)
+        .ok_or(PortfolioError::DivisionByZero("percentage of retirement value"))?
+        .checked_mul(Decimal::ONE_HUNDRED)
+        .ok_or(PortfolioError::Overflow("percentage of retirement value"))?;
 
-fn calc_number_of_shares_to_buy(stock: &Stock, total_value: f64, current_contribution_amount: f64) -> Option<CalculationResult>{
-    let target_allocation_as_decimal = stock.target_allocation / 100.0;
-    let new_number_of_shares = (target_allocation_as_decimal * total_value) / stock.quote;
-    let shares_to_buy = new_number_of_shares - (stock.number_of_shares as f64);
-    let cost = shares_to_buy * stock.quote;
-    
-    if shares_to_buy > 0.0 && cost < current_contribution_amount {
-        Some(CalculationResult{
-            symbol: stock.symbol.clone(),
-            new_number_of_shares: (shares_to_buy as i64),
-            cost: shares_to_buy * stock.quote,
-        })
-    } else if shares_to_buy > 0.0 {
-        Some(determine_result_based_on_contrib_amount(stock, current_contribution_amount))
-    }else {
-        None
-    }
-}
+    println!("Assuming a withdrawal rate of {}%, you need {} to retire", retirement_rule, target_retirement_portfolio_value);
+    println!("You will have contributed {}, which in {} years will be {}", present_value, years_to_pass, fully_grown_portfolio);
+    println!("This means once you are {}, you will be at {} % of your target", data.target_retirement_age, percentage_of_retirement_value);
+
+    let monte_carlo_result = monte_carlo::project(
+        total_value,
+        data.expected_contribution,
+        data.target_growth_rate,
+        data.return_volatility,
+        years_to_pass,
+        target_retirement_portfolio_value,
+        data.monte_carlo_seed,
+    )?;
+    println!(
+        "Monte Carlo projection: 10th percentile {}, median {}, 90th percentile {}",
+        monte_carlo_result.p10, monte_carlo_result.p50, monte_carlo_result.p90
+    );
+    println!(
+        "Probability of meeting your retirement target: {:.1}%",
+        monte_carlo_result.probability_of_success * 100.0
+    );
 
-fn determine_result_based_on_contrib_amount(stock: &Stock, current_contribution_amount: f64) -> CalculationResult{
-   let shares_to_buy = current_contribution_amount / stock.quote; 
-   CalculationResult{
-       symbol: stock.symbol.clone(),
-       new_number_of_shares: (shares_to_buy as i64),
-       cost: shares_to_buy * stock.quote,
-   }
+    Ok(())
 }
 
-fn print_where_to_contribute(data: Data){
-    let mut current_contribution_amount = data.expected_contribution;
-    let total_value = calc_portfolio_val(data.stocks.clone(), data.usd_to_cad_exchange_rate);
-    let mut results: Vec<CalculationResult> = data.stocks.iter().filter_map(|stock| calc_number_of_shares_to_buy(stock, total_value,current_contribution_amount)).collect();
-    results.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    println!("To fix allocations, make the following purchases");
-    for result in results {
-        if result.cost > current_contribution_amount { continue; }
-        println!("{}", result);
-        current_contribution_amount -= result.cost;
-    }
-
-    println!("Use extra contribution cash to do the following");
-    for stock in data.stocks {
-        if current_contribution_amount <= 0.0 { continue; }
-        let result = determine_result_based_on_contrib_amount(&stock, current_contribution_amount);
-        println!("{}", result);
-        current_contribution_amount -= result.cost;
+fn print_current_portfolio_state(
+    data: Data,
+    converter: &CurrencyConverter,
+    risk_free_rate: f64,
+    valuation_date: NaiveDate,
+) -> Result<(), PortfolioError> {
+    let total_value = calc_portfolio_val(&data, converter, risk_free_rate, valuation_date)?;
+    if total_value.is_zero() {
+        return Err(PortfolioError::DivisionByZero("current allocation"));
     }
-}
-
-fn print_how_close_to_retirement(data: Data){
-    let total_value = calc_portfolio_val(data.stocks, data.usd_to_cad_exchange_rate);
-    let new_value = total_value + data.expected_contribution;
-    let years_to_pass = data.target_retirement_age - data.current_age;
-    let multiplier = 1.0 + (data.target_growth_rate / 100.0);
-    let retirement_rule = 4.0;
-    let target_retirement_portfolio_value = data.annual_expenses / (retirement_rule / 100.0);
-    let fully_grown_portfolio = new_value * multiplier.powi(years_to_pass);
-    let percentage_of_retirement_value = fully_grown_portfolio / target_retirement_portfolio_value * 100.0;
-    println!("Assuming a withdrawal rate of {}%, you need {} to retire", retirement_rule, target_retirement_portfolio_value);
-    println!("You will have contributed {}, which in {} years will be {}", new_value, years_to_pass, fully_grown_portfolio);
-    println!("This means once you are {}, you will be at {} % of your target", data.target_retirement_age, percentage_of_retirement_value); 
-} 
-
-fn print_current_portfolio_state(data: Data){
-    let total_value = calc_portfolio_val(data.stocks.clone(), data.usd_to_cad_exchange_rate);
 
     println!("The portfolio state is the following:");
-    for stock in data.stocks {
-        let current_allocation = calc_value_of_stock(&stock, data.usd_to_cad_exchange_rate)/ total_value * 100.0;
+    for stock in &data.stocks {
+        let current_allocation = calc_value_of_stock(stock, converter)?
+            .checked_div(total_value)
+            .ok_or(PortfolioError::DivisionByZero("current allocation"))?
+            .checked_mul(Decimal::ONE_HUNDRED)
+            .ok_or(PortfolioError::Overflow("current allocation"))?;
         println!("You have {} shares in {} for an allocation of {}", stock.number_of_shares, stock.symbol, current_allocation);
     }
+    for option in &data.options {
+        let value = options::calc_value_of_option(option, risk_free_rate, valuation_date, converter)?;
+        let current_allocation = value
+            .checked_div(total_value)
+            .ok_or(PortfolioError::DivisionByZero("current allocation"))?
+            .checked_mul(Decimal::ONE_HUNDRED)
+            .ok_or(PortfolioError::Overflow("current allocation"))?;
+        println!(
+            "You have {} contracts on {} worth {} for an allocation of {}",
+            option.contracts, option.underlying_symbol, value, current_allocation
+        );
+    }
+    Ok(())
 }
 
-fn main() -> Result<()>{
+fn main() -> Result<(), Box<dyn Error>> {
+    let offline = std::env::args().any(|arg| arg == "--offline");
+
     let data_file = fs::read_to_string("data.json").expect("Unable to read file!");
-    let data: Data = serde_json::from_str(&data_file)?;
+    let mut data: Data = serde_json::from_str(&data_file)?;
 
-    print_current_portfolio_state(data.clone());
+    let config_file = fs::read_to_string("config.json").expect("Unable to read file!");
+    let config: Config = serde_json::from_str(&config_file)?;
+
+    let mut currency_converter = config.currency.clone();
+    quote_provider::refresh_live_quotes(&mut data, &config.quote_provider, &mut currency_converter, offline)?;
+
+    let valuation_date = chrono::Utc::now().date_naive();
+
+    print_current_portfolio_state(data.clone(), &currency_converter, config.risk_free_rate, valuation_date)?;
     println!("Figure out where to contribute:");
-    print_where_to_contribute(data.clone());
+    print_where_to_contribute(data.clone(), &config.rebalancing, &currency_converter, config.risk_free_rate, valuation_date)?;
     println!("Your distance to retirement:");
-    print_how_close_to_retirement(data);
+    print_how_close_to_retirement(data, &currency_converter, config.risk_free_rate, valuation_date)?;
 
     Ok(())
-}
\ No newline at end of file
+}
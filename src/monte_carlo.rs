@@ -0,0 +1,120 @@
+use crate::error::PortfolioError;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Normal};
+use rayon::prelude::*;
+use rust_decimal::prelude::*;
+
+const TRIALS: usize = 10_000;
+
+/// Terminal-value percentiles and probability of success across the simulated trials.
+pub struct MonteCarloResult {
+    pub p10: Decimal,
+    pub p50: Decimal,
+    pub p90: Decimal,
+    pub probability_of_success: f64,
+}
+
+/// Simulates `TRIALS` independent retirement paths, compounding `starting_value` year over
+/// year with a return drawn from N(`mean_annual_return_pct`, `annual_volatility_pct`) and an
+/// `expected_contribution` added at the start of each year. Each trial is seeded
+/// deterministically from `seed` so parallel runs are reproducible.
+pub fn project(
+    starting_value: Decimal,
+    expected_contribution: Decimal,
+    mean_annual_return_pct: f64,
+    annual_volatility_pct: f64,
+    years_to_pass: i32,
+    target_retirement_portfolio_value: Decimal,
+    seed: u64,
+) -> Result<MonteCarloResult, PortfolioError> {
+    if years_to_pass <= 0 {
+        let probability_of_success = if starting_value >= target_retirement_portfolio_value { 1.0 } else { 0.0 };
+        return Ok(MonteCarloResult {
+            p10: starting_value,
+            p50: starting_value,
+            p90: starting_value,
+            probability_of_success,
+        });
+    }
+
+    let mean = mean_annual_return_pct / 100.0;
+    let sigma = annual_volatility_pct / 100.0;
+    let normal = Normal::new(mean, sigma).map_err(|_| PortfolioError::Overflow("return distribution"))?;
+
+    let starting_value = starting_value.to_f64().ok_or(PortfolioError::Overflow("starting value"))?;
+    let expected_contribution = expected_contribution.to_f64().ok_or(PortfolioError::Overflow("expected contribution"))?;
+    let target_value = target_retirement_portfolio_value
+        .to_f64()
+        .ok_or(PortfolioError::Overflow("target retirement value"))?;
+
+    let mut terminal_values: Vec<f64> = (0..TRIALS)
+        .into_par_iter()
+        .map(|trial_index| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(trial_index as u64));
+            let mut value = starting_value;
+            for _ in 0..years_to_pass {
+                value += expected_contribution;
+                let annual_return: f64 = normal.sample(&mut rng);
+                value = (value * (1.0 + annual_return)).max(0.0);
+            }
+            value
+        })
+        .collect();
+    terminal_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let percentile = |p: f64| -> Result<Decimal, PortfolioError> {
+        let index = (((terminal_values.len() - 1) as f64) * p).round() as usize;
+        Decimal::from_f64_retain(terminal_values[index]).ok_or(PortfolioError::Overflow("percentile"))
+    };
+
+    let successes = terminal_values.iter().filter(|value| **value >= target_value).count();
+    let probability_of_success = successes as f64 / terminal_values.len() as f64;
+
+    Ok(MonteCarloResult {
+        p10: percentile(0.10)?,
+        p50: percentile(0.50)?,
+        p90: percentile(0.90)?,
+        probability_of_success,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_years_short_circuits_to_starting_value() {
+        let result = project(Decimal::from(1000), Decimal::ZERO, 10.0, 15.0, 0, Decimal::from(500), 1).unwrap();
+
+        assert_eq!(result.p10, Decimal::from(1000));
+        assert_eq!(result.p50, Decimal::from(1000));
+        assert_eq!(result.p90, Decimal::from(1000));
+        assert_eq!(result.probability_of_success, 1.0);
+    }
+
+    #[test]
+    fn zero_years_below_target_is_a_failure() {
+        let result = project(Decimal::from(1000), Decimal::ZERO, 10.0, 15.0, 0, Decimal::from(2000), 1).unwrap();
+
+        assert_eq!(result.probability_of_success, 0.0);
+    }
+
+    #[test]
+    fn zero_volatility_collapses_percentiles_to_the_deterministic_path() {
+        // With no volatility every trial takes the same path: 1000 compounded once at 10% -> 1100.
+        let result = project(Decimal::from(1000), Decimal::ZERO, 10.0, 0.0, 1, Decimal::from(1050), 1).unwrap();
+
+        assert_eq!(result.p10, Decimal::from(1100));
+        assert_eq!(result.p50, Decimal::from(1100));
+        assert_eq!(result.p90, Decimal::from(1100));
+        assert_eq!(result.probability_of_success, 1.0);
+    }
+
+    #[test]
+    fn zero_volatility_below_target_fails_every_trial() {
+        let result = project(Decimal::from(1000), Decimal::ZERO, 10.0, 0.0, 1, Decimal::from(1200), 1).unwrap();
+
+        assert_eq!(result.probability_of_success, 0.0);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::currency::CurrencyConverter;
+use crate::error::PortfolioError;
+use chrono::NaiveDate;
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Contracts are quoted per share but settle on 100 shares of the underlying.
+const CONTRACT_MULTIPLIER: i64 = 100;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallOrPut {
+    Call,
+    Put,
+}
+
+/// A single options position: underlying symbol, strike, expiry, and number of contracts.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OptionHolding {
+    pub underlying_symbol: String,
+    pub underlying_quote: Decimal,
+    pub strike: Decimal,
+    pub expiry: NaiveDate,
+    pub call_or_put: CallOrPut,
+    pub contracts: i64,
+    /// Assumed volatility for this holding, as a percentage (e.g. `20.0` for 20%).
+    pub implied_volatility: f64,
+    pub currency: String,
+}
+
+/// Standard-normal CDF via the Abramowitz & Stegun erf approximation (7.1.26).
+fn standard_normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    const A1: f64 = 0.254829592;
+    const A2: f64 = -0.284496736;
+    const A3: f64 = 1.421413741;
+    const A4: f64 = -1.453152027;
+    const A5: f64 = 1.061405429;
+    const P: f64 = 0.3275911;
+
+    let t = 1.0 / (1.0 + P * x);
+    let poly = ((((A5 * t + A4) * t + A3) * t + A2) * t + A1) * t;
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+/// Prices one share's worth of an option via the closed-form Black-Scholes model, falling back to
+/// intrinsic value once the option has expired (`years_to_expiry <= 0`).
+fn black_scholes_price_per_share(
+    spot: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    sigma: f64,
+    years_to_expiry: f64,
+    call_or_put: CallOrPut,
+) -> f64 {
+    if years_to_expiry <= 0.0 {
+        return match call_or_put {
+            CallOrPut::Call => (spot - strike).max(0.0),
+            CallOrPut::Put => (strike - spot).max(0.0),
+        };
+    }
+
+    let d1 = ((spot / strike).ln() + (risk_free_rate + sigma * sigma / 2.0) * years_to_expiry)
+        / (sigma * years_to_expiry.sqrt());
+    let d2 = d1 - sigma * years_to_expiry.sqrt();
+    let discounted_strike = strike * (-risk_free_rate * years_to_expiry).exp();
+
+    match call_or_put {
+        CallOrPut::Call => spot * standard_normal_cdf(d1) - discounted_strike * standard_normal_cdf(d2),
+        CallOrPut::Put => discounted_strike * standard_normal_cdf(-d2) - spot * standard_normal_cdf(-d1),
+    }
+}
+
+/// Values an options holding in its quote currency, converted to the portfolio's base currency.
+/// `risk_free_rate` is a percentage (e.g. `5.0` for 5%), matching the rest of this codebase's
+/// percentage-valued config fields.
+pub fn calc_value_of_option(
+    option: &OptionHolding,
+    risk_free_rate: f64,
+    valuation_date: NaiveDate,
+    converter: &CurrencyConverter,
+) -> Result<Decimal, PortfolioError> {
+    let spot = option.underlying_quote.to_f64().ok_or(PortfolioError::Overflow("underlying quote"))?;
+    let strike = option.strike.to_f64().ok_or(PortfolioError::Overflow("strike"))?;
+    let years_to_expiry = (option.expiry - valuation_date).num_days() as f64 / 365.25;
+
+    let price_per_share = black_scholes_price_per_share(
+        spot,
+        strike,
+        risk_free_rate / 100.0,
+        option.implied_volatility / 100.0,
+        years_to_expiry,
+        option.call_or_put,
+    );
+    let price_per_share =
+        Decimal::from_f64(price_per_share).ok_or(PortfolioError::Overflow("option price"))?;
+
+    let contract_value = price_per_share
+        .checked_mul(Decimal::from(CONTRACT_MULTIPLIER))
+        .ok_or(PortfolioError::Overflow("option contract value"))?
+        .checked_mul(Decimal::from(option.contracts))
+        .ok_or(PortfolioError::Overflow("option position value"))?;
+    converter.convert(contract_value, &option.currency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_the_money_call_matches_reference_price() {
+        // S=100, K=100, r=5%, sigma=20%, T=1y -> textbook Black-Scholes price ~10.4506.
+        let price = black_scholes_price_per_share(100.0, 100.0, 0.05, 0.2, 1.0, CallOrPut::Call);
+        assert!((price - 10.4506).abs() < 0.001, "got {}", price);
+    }
+
+    #[test]
+    fn at_the_money_put_matches_reference_price() {
+        // Same inputs as the call above -> textbook put price ~5.5735.
+        let price = black_scholes_price_per_share(100.0, 100.0, 0.05, 0.2, 1.0, CallOrPut::Put);
+        assert!((price - 5.5735).abs() < 0.001, "got {}", price);
+    }
+
+    #[test]
+    fn expired_call_falls_back_to_intrinsic_value() {
+        let in_the_money = black_scholes_price_per_share(120.0, 100.0, 0.05, 0.2, 0.0, CallOrPut::Call);
+        assert_eq!(in_the_money, 20.0);
+
+        let out_of_the_money = black_scholes_price_per_share(80.0, 100.0, 0.05, 0.2, 0.0, CallOrPut::Call);
+        assert_eq!(out_of_the_money, 0.0);
+    }
+
+    #[test]
+    fn expired_put_falls_back_to_intrinsic_value() {
+        let in_the_money = black_scholes_price_per_share(80.0, 100.0, 0.05, 0.2, 0.0, CallOrPut::Put);
+        assert_eq!(in_the_money, 20.0);
+
+        let out_of_the_money = black_scholes_price_per_share(120.0, 100.0, 0.05, 0.2, 0.0, CallOrPut::Put);
+        assert_eq!(out_of_the_money, 0.0);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::error::PortfolioError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CACHE_PATH: &str = "quote_cache.json";
+const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// A single fetched quote, cached alongside the time it was fetched.
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedQuote {
+    price: Decimal,
+    fetched_at_unix: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct QuoteCache {
+    quotes: HashMap<String, CachedQuote>,
+}
+
+impl QuoteCache {
+    fn load() -> Self {
+        fs::read_to_string(CACHE_PATH)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let Ok(serialized) = serde_json::to_string(self) {
+            let _ = fs::write(CACHE_PATH, serialized);
+        }
+    }
+
+    fn get(&self, symbol: &str) -> Option<Decimal> {
+        let cached = self.quotes.get(symbol)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        if now.saturating_sub(cached.fetched_at_unix) < CACHE_TTL.as_secs() {
+            Some(cached.price)
+        } else {
+            None
+        }
+    }
+
+    fn put(&mut self, symbol: &str, price: Decimal) {
+        let fetched_at_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.quotes.insert(symbol.to_string(), CachedQuote { price, fetched_at_unix });
+    }
+}
+
+/// Which market-data provider to fetch live quotes from.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteProviderKind {
+    AlphaVantage,
+    Finnhub,
+    TwelveData,
+}
+
+/// Provider selection and credentials, read from the `quote_provider` config section.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QuoteProviderConfig {
+    pub provider: QuoteProviderKind,
+    pub alphavantage: Option<AlphaVantageConfig>,
+    pub finnhub: Option<FinnhubConfig>,
+    pub twelvedata: Option<TwelveDataConfig>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AlphaVantageConfig {
+    pub api_key: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FinnhubConfig {
+    pub api_key: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TwelveDataConfig {
+    pub api_key: String,
+}
+
+/// Fetches the latest price for a symbol and exchange rates between currency pairs.
+pub trait QuoteProvider {
+    fn fetch_quote(&self, symbol: &str) -> Result<Decimal, PortfolioError>;
+    fn fetch_exchange_rate(&self, from_currency: &str, to_currency: &str) -> Result<Decimal, PortfolioError>;
+}
+
+struct AlphaVantageProvider {
+    api_key: String,
+}
+
+impl QuoteProvider for AlphaVantageProvider {
+    fn fetch_quote(&self, symbol: &str) -> Result<Decimal, PortfolioError> {
+        let url = format!(
+            "https://www.alphavantage.co/query?function=GLOBAL_QUOTE&symbol={}&apikey={}",
+            symbol, self.api_key
+        );
+        let body = reqwest::blocking::get(&url)
+            .map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?
+            .text()
+            .map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&body).map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        let price = parsed["Global Quote"]["05. price"]
+            .as_str()
+            .ok_or(PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        price.parse().map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))
+    }
+
+    fn fetch_exchange_rate(&self, from_currency: &str, to_currency: &str) -> Result<Decimal, PortfolioError> {
+        let pair = format!("{}/{}", from_currency, to_currency);
+        let url = format!(
+            "https://www.alphavantage.co/query?function=CURRENCY_EXCHANGE_RATE&from_currency={}&to_currency={}&apikey={}",
+            from_currency, to_currency, self.api_key
+        );
+        let body = reqwest::blocking::get(&url)
+            .map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?
+            .text()
+            .map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&body).map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?;
+        let rate = parsed["Realtime Currency Exchange Rate"]["5. Exchange Rate"]
+            .as_str()
+            .ok_or(PortfolioError::QuoteUnavailable(pair.clone()))?;
+        rate.parse().map_err(|_| PortfolioError::QuoteUnavailable(pair))
+    }
+}
+
+struct FinnhubProvider {
+    api_key: String,
+}
+
+impl QuoteProvider for FinnhubProvider {
+    fn fetch_quote(&self, symbol: &str) -> Result<Decimal, PortfolioError> {
+        let url = format!("https://finnhub.io/api/v1/quote?symbol={}&token={}", symbol, self.api_key);
+        let body = reqwest::blocking::get(&url)
+            .map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?
+            .text()
+            .map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&body).map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        let price = parsed["c"].as_f64().ok_or(PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        Decimal::from_f64_retain(price).ok_or(PortfolioError::QuoteUnavailable(symbol.to_string()))
+    }
+
+    fn fetch_exchange_rate(&self, from_currency: &str, to_currency: &str) -> Result<Decimal, PortfolioError> {
+        let pair = format!("{}/{}", from_currency, to_currency);
+        let url = format!("https://finnhub.io/api/v1/forex/rates?base={}&token={}", from_currency, self.api_key);
+        let body = reqwest::blocking::get(&url)
+            .map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?
+            .text()
+            .map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&body).map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?;
+        let rate = parsed["quote"][to_currency]
+            .as_f64()
+            .ok_or(PortfolioError::QuoteUnavailable(pair.clone()))?;
+        Decimal::from_f64_retain(rate).ok_or(PortfolioError::QuoteUnavailable(pair))
+    }
+}
+
+struct TwelveDataProvider {
+    api_key: String,
+}
+
+impl QuoteProvider for TwelveDataProvider {
+    fn fetch_quote(&self, symbol: &str) -> Result<Decimal, PortfolioError> {
+        let url = format!("https://api.twelvedata.com/price?symbol={}&apikey={}", symbol, self.api_key);
+        let body = reqwest::blocking::get(&url)
+            .map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?
+            .text()
+            .map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&body).map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        let price = parsed["price"]
+            .as_str()
+            .ok_or(PortfolioError::QuoteUnavailable(symbol.to_string()))?;
+        price.parse().map_err(|_| PortfolioError::QuoteUnavailable(symbol.to_string()))
+    }
+
+    fn fetch_exchange_rate(&self, from_currency: &str, to_currency: &str) -> Result<Decimal, PortfolioError> {
+        let pair = format!("{}/{}", from_currency, to_currency);
+        let url = format!("https://api.twelvedata.com/exchange_rate?symbol={}&apikey={}", pair, self.api_key);
+        let body = reqwest::blocking::get(&url)
+            .map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?
+            .text()
+            .map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?;
+        let parsed: serde_json::Value =
+            serde_json::from_str(&body).map_err(|_| PortfolioError::QuoteUnavailable(pair.clone()))?;
+        let rate = parsed["rate"]
+            .as_f64()
+            .ok_or(PortfolioError::QuoteUnavailable(pair.clone()))?;
+        Decimal::from_f64_retain(rate).ok_or(PortfolioError::QuoteUnavailable(pair))
+    }
+}
+
+fn build_provider(config: &QuoteProviderConfig) -> Result<Box<dyn QuoteProvider>, PortfolioError> {
+    match config.provider {
+        QuoteProviderKind::AlphaVantage => {
+            let api_key = config
+                .alphavantage
+                .as_ref()
+                .ok_or(PortfolioError::MissingProviderConfig("alphavantage"))?
+                .api_key
+                .clone();
+            Ok(Box::new(AlphaVantageProvider { api_key }))
+        }
+        QuoteProviderKind::Finnhub => {
+            let api_key = config
+                .finnhub
+                .as_ref()
+                .ok_or(PortfolioError::MissingProviderConfig("finnhub"))?
+                .api_key
+                .clone();
+            Ok(Box::new(FinnhubProvider { api_key }))
+        }
+        QuoteProviderKind::TwelveData => {
+            let api_key = config
+                .twelvedata
+                .as_ref()
+                .ok_or(PortfolioError::MissingProviderConfig("twelvedata"))?
+                .api_key
+                .clone();
+            Ok(Box::new(TwelveDataProvider { api_key }))
+        }
+    }
+}
+
+/// Fetches a quote for `symbol`, preferring a fresh cache entry over a network call.
+pub fn fetch_quote_cached(config: &QuoteProviderConfig, cache: &mut QuoteCache, symbol: &str) -> Result<Decimal, PortfolioError> {
+    if let Some(price) = cache.get(symbol) {
+        return Ok(price);
+    }
+    let provider = build_provider(config)?;
+    let price = provider.fetch_quote(symbol)?;
+    cache.put(symbol, price);
+    Ok(price)
+}
+
+/// Fetches the exchange rate from `from_currency` to `to_currency`, preferring a fresh cache
+/// entry over a network call.
+pub fn fetch_exchange_rate_cached(
+    config: &QuoteProviderConfig,
+    cache: &mut QuoteCache,
+    from_currency: &str,
+    to_currency: &str,
+) -> Result<Decimal, PortfolioError> {
+    let cache_key = format!("{}/{}", from_currency, to_currency);
+    if let Some(rate) = cache.get(&cache_key) {
+        return Ok(rate);
+    }
+    let provider = build_provider(config)?;
+    let rate = provider.fetch_exchange_rate(from_currency, to_currency)?;
+    cache.put(&cache_key, rate);
+    Ok(rate)
+}
+
+/// Fills in `Stock.quote`, each option's `underlying_quote`, and the base-currency exchange
+/// rate for every non-base currency held, persisting the refreshed cache to disk. When
+/// `offline` is true this is a no-op and the values already in `data.json` / the config's
+/// `currency` section are left untouched.
+pub fn refresh_live_quotes(
+    data: &mut crate::Data,
+    config: &QuoteProviderConfig,
+    converter: &mut crate::currency::CurrencyConverter,
+    offline: bool,
+) -> Result<(), PortfolioError> {
+    if offline {
+        return Ok(());
+    }
+
+    let mut cache = QuoteCache::load();
+    for stock in data.stocks.iter_mut() {
+        stock.quote = fetch_quote_cached(config, &mut cache, &stock.symbol)?;
+    }
+    for option in data.options.iter_mut() {
+        option.underlying_quote = fetch_quote_cached(config, &mut cache, &option.underlying_symbol)?;
+    }
+
+    let mut held_currencies: Vec<&str> = data
+        .stocks
+        .iter()
+        .map(|stock| stock.currency.as_str())
+        .chain(data.options.iter().map(|option| option.currency.as_str()))
+        .collect();
+    held_currencies.sort_unstable();
+    held_currencies.dedup();
+    for currency in held_currencies {
+        if currency == converter.base_currency {
+            continue;
+        }
+        let rate = fetch_exchange_rate_cached(config, &mut cache, currency, &converter.base_currency)?;
+        converter.rates.insert(currency.to_string(), rate);
+    }
+
+    cache.save();
+    Ok(())
+}
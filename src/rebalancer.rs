@@ -0,0 +1,413 @@
+use crate::currency::CurrencyConverter;
+use crate::error::PortfolioError;
+use crate::{Data, Stock};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Flat + percentage broker commission, with a minimum fee floor.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CommissionConfig {
+    pub flat_fee: Decimal,
+    pub percentage_fee: f64,
+    pub minimum_fee: Decimal,
+}
+
+impl CommissionConfig {
+    fn fee_for(&self, trade_value: Decimal) -> Result<Decimal, PortfolioError> {
+        let percentage_as_decimal = Decimal::from_f64(self.percentage_fee)
+            .ok_or(PortfolioError::Overflow("commission percentage"))?
+            .checked_div(Decimal::ONE_HUNDRED)
+            .ok_or(PortfolioError::DivisionByZero("commission percentage"))?;
+        let percentage_fee = trade_value
+            .checked_mul(percentage_as_decimal)
+            .ok_or(PortfolioError::Overflow("commission"))?;
+        let fee = self
+            .flat_fee
+            .checked_add(percentage_fee)
+            .ok_or(PortfolioError::Overflow("commission"))?;
+        Ok(fee.max(self.minimum_fee))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RebalancingConfig {
+    pub commission: CommissionConfig,
+    pub minimum_trade_volume: Decimal,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TradeAction {
+    Buy,
+    Sell,
+}
+
+impl fmt::Display for TradeAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TradeAction::Buy => write!(f, "Buy"),
+            TradeAction::Sell => write!(f, "Sell"),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct CalculationResult {
+    pub symbol: String,
+    pub action: TradeAction,
+    pub number_of_shares: i64,
+    pub cost: Decimal,
+}
+
+impl fmt::Display for CalculationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} shares in {} for {}", self.action, self.number_of_shares, self.symbol, self.cost)
+    }
+}
+
+fn whole_shares(shares: Decimal) -> Result<i64, PortfolioError> {
+    shares
+        .round_dp_with_strategy(0, RoundingStrategy::ToZero)
+        .to_i64()
+        .ok_or(PortfolioError::Overflow("share count"))
+}
+
+fn allocation_percentage(value: Decimal, total_value: Decimal) -> Result<Decimal, PortfolioError> {
+    if total_value.is_zero() {
+        return Err(PortfolioError::DivisionByZero("allocation percentage"));
+    }
+    value
+        .checked_div(total_value)
+        .ok_or(PortfolioError::DivisionByZero("allocation percentage"))?
+        .checked_mul(Decimal::ONE_HUNDRED)
+        .ok_or(PortfolioError::Overflow("allocation percentage"))
+}
+
+fn value_at_percentage(percentage: Decimal, total_value: Decimal) -> Result<Decimal, PortfolioError> {
+    percentage
+        .checked_div(Decimal::ONE_HUNDRED)
+        .ok_or(PortfolioError::DivisionByZero("percentage"))?
+        .checked_mul(total_value)
+        .ok_or(PortfolioError::Overflow("percentage value"))
+}
+
+/// `stock.quote` is denominated in the stock's own currency, but trade sizing happens against
+/// values already converted to the base currency (`excess_value`, `spendable`, `total_value`) —
+/// convert the quote to the base currency before it's used alongside them.
+fn quote_in_base_currency(stock: &Stock, converter: &CurrencyConverter) -> Result<Decimal, PortfolioError> {
+    converter.convert(stock.quote, &stock.currency)
+}
+
+/// Sells enough of a stock that has drifted past its max allocation band to bring it back to
+/// target, provided the sale's value before commission clears the minimum trade volume and the
+/// proceeds after commission are still positive.
+fn sell_down_to_target(
+    data: &Data,
+    total_value: Decimal,
+    config: &RebalancingConfig,
+    converter: &CurrencyConverter,
+) -> Result<Vec<CalculationResult>, PortfolioError> {
+    let mut results = Vec::new();
+    for stock in &data.stocks {
+        let current_value = crate::calc_value_of_stock(stock, converter)?;
+        let current_allocation = allocation_percentage(current_value, total_value)?;
+        let max_allocation =
+            Decimal::from_f64(stock.max_allocation).ok_or(PortfolioError::Overflow("max allocation"))?;
+        if current_allocation <= max_allocation {
+            continue;
+        }
+
+        let target_allocation =
+            Decimal::from_f64(stock.target_allocation).ok_or(PortfolioError::Overflow("target allocation"))?;
+        let target_value = value_at_percentage(target_allocation, total_value)?;
+        let excess_value = current_value
+            .checked_sub(target_value)
+            .ok_or(PortfolioError::Overflow("excess value"))?;
+        let quote = quote_in_base_currency(stock, converter)?;
+        if quote.is_zero() {
+            return Err(PortfolioError::DivisionByZero("shares to sell"));
+        }
+        let shares_to_sell = whole_shares(
+            excess_value
+                .checked_div(quote)
+                .ok_or(PortfolioError::DivisionByZero("shares to sell"))?,
+        )?;
+        if shares_to_sell <= 0 {
+            continue;
+        }
+
+        let proceeds = quote
+            .checked_mul(Decimal::from(shares_to_sell))
+            .ok_or(PortfolioError::Overflow("sale proceeds"))?;
+        if proceeds < config.minimum_trade_volume {
+            continue;
+        }
+        let fee = config.commission.fee_for(proceeds)?;
+        let net_proceeds = proceeds.checked_sub(fee).ok_or(PortfolioError::Overflow("net proceeds"))?;
+        if net_proceeds <= Decimal::ZERO {
+            continue;
+        }
+
+        results.push(CalculationResult {
+            symbol: stock.symbol.clone(),
+            action: TradeAction::Sell,
+            number_of_shares: shares_to_sell,
+            cost: net_proceeds,
+        });
+    }
+    Ok(results)
+}
+
+/// Allocates `data.expected_contribution` across stocks that have drifted below their min
+/// allocation band, in proportion to each stock's shortfall from target, only recommending a
+/// purchase when the trade value before commission clears the minimum trade volume and the
+/// post-commission cost fits the remaining budget.
+fn buy_up_to_target(
+    data: &Data,
+    total_value: Decimal,
+    config: &RebalancingConfig,
+    converter: &CurrencyConverter,
+) -> Result<Vec<CalculationResult>, PortfolioError> {
+    let mut shortfalls = Vec::new();
+    for stock in &data.stocks {
+        let current_value = crate::calc_value_of_stock(stock, converter)?;
+        let current_allocation = allocation_percentage(current_value, total_value)?;
+        let min_allocation =
+            Decimal::from_f64(stock.min_allocation).ok_or(PortfolioError::Overflow("min allocation"))?;
+        if current_allocation >= min_allocation {
+            continue;
+        }
+        let target_allocation =
+            Decimal::from_f64(stock.target_allocation).ok_or(PortfolioError::Overflow("target allocation"))?;
+        let shortfall = target_allocation
+            .checked_sub(current_allocation)
+            .ok_or(PortfolioError::Overflow("shortfall"))?;
+        if shortfall > Decimal::ZERO {
+            shortfalls.push((stock, shortfall));
+        }
+    }
+
+    let total_shortfall = shortfalls
+        .iter()
+        .try_fold(Decimal::ZERO, |acc, (_, shortfall)| {
+            acc.checked_add(*shortfall).ok_or(PortfolioError::Overflow("total shortfall"))
+        })?;
+    if total_shortfall.is_zero() {
+        return Ok(Vec::new());
+    }
+
+    let mut results = Vec::new();
+    let mut remaining_contribution = data.expected_contribution;
+    for (stock, shortfall) in shortfalls {
+        if remaining_contribution <= Decimal::ZERO {
+            break;
+        }
+        let quote = quote_in_base_currency(stock, converter)?;
+        if quote.is_zero() {
+            return Err(PortfolioError::DivisionByZero("shares to buy"));
+        }
+
+        let weight = shortfall
+            .checked_div(total_shortfall)
+            .ok_or(PortfolioError::DivisionByZero("shortfall weight"))?;
+        let budget = weight
+            .checked_mul(data.expected_contribution)
+            .ok_or(PortfolioError::Overflow("allocated budget"))?
+            .min(remaining_contribution);
+
+        let estimated_fee = config.commission.fee_for(budget)?;
+        let spendable = budget
+            .checked_sub(estimated_fee)
+            .ok_or(PortfolioError::Overflow("spendable budget"))?;
+        if spendable <= Decimal::ZERO {
+            continue;
+        }
+        let shares_to_buy = whole_shares(
+            spendable
+                .checked_div(quote)
+                .ok_or(PortfolioError::DivisionByZero("shares to buy"))?,
+        )?;
+        if shares_to_buy <= 0 {
+            continue;
+        }
+
+        let trade_value = quote
+            .checked_mul(Decimal::from(shares_to_buy))
+            .ok_or(PortfolioError::Overflow("trade value"))?;
+        if trade_value < config.minimum_trade_volume {
+            continue;
+        }
+        let fee = config.commission.fee_for(trade_value)?;
+        let total_cost = trade_value.checked_add(fee).ok_or(PortfolioError::Overflow("total cost"))?;
+        if total_cost > remaining_contribution {
+            continue;
+        }
+
+        remaining_contribution = remaining_contribution
+            .checked_sub(total_cost)
+            .ok_or(PortfolioError::Overflow("remaining contribution"))?;
+        results.push(CalculationResult {
+            symbol: stock.symbol.clone(),
+            action: TradeAction::Buy,
+            number_of_shares: shares_to_buy,
+            cost: total_cost,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Rebalances the portfolio against each stock's min/max allocation band: positions that have
+/// drifted above their band are sold back to target, and `expected_contribution` is spent on
+/// positions that have drifted below their band, proportional to their shortfall.
+pub fn rebalance(
+    data: &Data,
+    total_value: Decimal,
+    config: &RebalancingConfig,
+    converter: &CurrencyConverter,
+) -> Result<Vec<CalculationResult>, PortfolioError> {
+    let mut results = sell_down_to_target(data, total_value, config, converter)?;
+    results.extend(buy_up_to_target(data, total_value, config, converter)?);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Data, Stock};
+
+    fn stock(symbol: &str, quote: i64, shares: i64, target: f64, min: f64, max: f64) -> Stock {
+        stock_with_currency(symbol, quote, shares, target, min, max, "USD")
+    }
+
+    fn stock_with_currency(
+        symbol: &str,
+        quote: i64,
+        shares: i64,
+        target: f64,
+        min: f64,
+        max: f64,
+        currency: &str,
+    ) -> Stock {
+        Stock {
+            symbol: symbol.to_string(),
+            quote: Decimal::from(quote),
+            number_of_shares: shares,
+            target_allocation: target,
+            min_allocation: min,
+            max_allocation: max,
+            currency: currency.to_string(),
+        }
+    }
+
+    fn data(stocks: Vec<Stock>, expected_contribution: i64) -> Data {
+        Data {
+            stocks,
+            options: Vec::new(),
+            annual_expenses: Decimal::ZERO,
+            target_retirement_age: 0,
+            current_age: 0,
+            target_growth_rate: 0.0,
+            return_volatility: 0.0,
+            monte_carlo_seed: 0,
+            expected_contribution: Decimal::from(expected_contribution),
+        }
+    }
+
+    fn converter() -> CurrencyConverter {
+        CurrencyConverter { base_currency: "USD".to_string(), rates: std::collections::HashMap::new() }
+    }
+
+    fn converter_with_rate(base_currency: &str, currency: &str, rate: Decimal) -> CurrencyConverter {
+        let mut rates = std::collections::HashMap::new();
+        rates.insert(currency.to_string(), rate);
+        CurrencyConverter { base_currency: base_currency.to_string(), rates }
+    }
+
+    fn no_commission(minimum_trade_volume: i64) -> RebalancingConfig {
+        RebalancingConfig {
+            commission: CommissionConfig { flat_fee: Decimal::ZERO, percentage_fee: 0.0, minimum_fee: Decimal::ZERO },
+            minimum_trade_volume: Decimal::from(minimum_trade_volume),
+        }
+    }
+
+    #[test]
+    fn sell_down_to_target_only_trims_stocks_past_max_allocation() {
+        // A: 800/1000 = 80% > max 60%, should be trimmed back to target 50%.
+        // B: 500/1000 = 50%, within its 60% max, should be left alone.
+        let d = data(
+            vec![
+                stock("A", 10, 80, 50.0, 0.0, 60.0),
+                stock("B", 10, 50, 50.0, 0.0, 60.0),
+            ],
+            0,
+        );
+        let results = sell_down_to_target(&d, Decimal::from(1000), &no_commission(50), &converter()).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].symbol, "A");
+        assert_eq!(results[0].action, TradeAction::Sell);
+        assert_eq!(results[0].number_of_shares, 30);
+    }
+
+    #[test]
+    fn sell_down_to_target_converts_quote_to_base_currency_before_sizing_shares() {
+        // Base=CAD, stock quoted in USD at 10 (rate 1.35): 80 shares is a 1080 CAD position.
+        // 100% > max 60%, target 50% -> 540 CAD excess -> 540 / (10 * 1.35) = 40 shares, not 54.
+        let d = data(vec![stock_with_currency("A", 10, 80, 50.0, 0.0, 60.0, "USD")], 0);
+        let converter = converter_with_rate("CAD", "USD", Decimal::new(135, 2));
+        let results = sell_down_to_target(&d, Decimal::from(1080), &no_commission(50), &converter).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].number_of_shares, 40);
+    }
+
+    #[test]
+    fn sell_down_to_target_skips_trades_below_minimum_trade_volume() {
+        // Same drift as above (300 in proceeds), but the minimum trade volume is set above it.
+        let d = data(vec![stock("A", 10, 80, 50.0, 0.0, 60.0)], 0);
+        let results = sell_down_to_target(&d, Decimal::from(1000), &no_commission(1000), &converter()).unwrap();
+
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn buy_up_to_target_only_funds_stocks_below_min_allocation() {
+        // A: 200/1000 = 20% < min 30%, should receive the full contribution.
+        // B: 500/1000 = 50%, within its 30% min, should be left alone.
+        let d = data(
+            vec![
+                stock("A", 10, 20, 50.0, 30.0, 100.0),
+                stock("B", 10, 50, 50.0, 30.0, 100.0),
+            ],
+            300,
+        );
+        let results = buy_up_to_target(&d, Decimal::from(1000), &no_commission(50), &converter()).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].symbol, "A");
+        assert_eq!(results[0].action, TradeAction::Buy);
+        assert_eq!(results[0].number_of_shares, 30);
+    }
+
+    #[test]
+    fn buy_up_to_target_converts_quote_to_base_currency_before_sizing_shares() {
+        // Base=CAD, stock quoted in USD at 10 (rate 1.35): 20 shares is a 270 CAD position.
+        // 27% < min 30%, so the whole 405 CAD contribution goes to A -> 405 / (10 * 1.35) = 30 shares, not 40.
+        let d = data(vec![stock_with_currency("A", 10, 20, 50.0, 30.0, 100.0, "USD")], 405);
+        let converter = converter_with_rate("CAD", "USD", Decimal::new(135, 2));
+        let results = buy_up_to_target(&d, Decimal::from(1000), &no_commission(50), &converter).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].number_of_shares, 30);
+    }
+
+    #[test]
+    fn buy_up_to_target_skips_trades_below_minimum_trade_volume() {
+        // Same shortfall as above (300 trade value), but the minimum trade volume is set above it.
+        let d = data(vec![stock("A", 10, 20, 50.0, 30.0, 100.0)], 300);
+        let results = buy_up_to_target(&d, Decimal::from(1000), &no_commission(1000), &converter()).unwrap();
+
+        assert!(results.is_empty());
+    }
+}